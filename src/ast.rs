@@ -0,0 +1,80 @@
+use crate::token::Loc;
+
+/// A resolved type reference, e.g. `string`, `list<i32>`, `map<string, binary>`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeRef {
+    Simple(String),
+    List(Box<TypeRef>),
+    Set(Box<TypeRef>),
+    Map(Box<TypeRef>, Box<TypeRef>),
+    Optional(Box<TypeRef>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lang {
+    Cpp,
+    Java,
+    ObjC,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub type_ref: TypeRef,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub type_ref: TypeRef,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Method {
+    pub name: String,
+    pub is_static: bool,
+    pub params: Vec<Param>,
+    pub return_type: Option<TypeRef>,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub name: String,
+    pub values: Vec<String>,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub name: String,
+    pub langs: Vec<Lang>,
+    pub fields: Vec<Field>,
+    pub deriving: Vec<String>,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+    pub name: String,
+    pub langs: Vec<Lang>,
+    pub methods: Vec<Method>,
+    pub loc: Loc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Directive {
+    Import(String, Loc),
+    Extern(String, Loc),
+}
+
+/// A single top-level Djinni declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decl {
+    Enum(Enum),
+    Record(Record),
+    Interface(Interface),
+    Directive(Directive),
+}
@@ -1,3 +1,5 @@
+mod ast;
+mod parser;
 mod token;
 
 use token::tokenize;
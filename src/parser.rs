@@ -0,0 +1,586 @@
+use crate::ast::{Decl, Directive, Enum, Field, Interface, Lang, Method, Param, Record, TypeRef};
+use crate::token::{Loc, Token};
+
+/// A parse failure, carrying the source location so callers can report
+/// e.g. "expected `}` at line/column".
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub expected: String,
+    pub found: Option<Token>,
+    pub loc: Loc,
+}
+
+/// An immutable view into the remaining tokens. Every `parse_*` function
+/// takes a cursor and returns the cursor positioned just past what it
+/// consumed, so parsing composes by threading the cursor through.
+#[derive(Clone, Copy)]
+struct Cursor<'a> {
+    tokens: &'a [Token],
+}
+
+type ParseResult<'a, T> = Result<(T, Cursor<'a>), ParseError>;
+
+impl<'a> Cursor<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Cursor { tokens }
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.first()
+    }
+
+    fn loc(&self) -> Loc {
+        self.tokens.first().map(Token::loc).unwrap_or_default()
+    }
+}
+
+fn bump<'a>(cursor: Cursor<'a>) -> Option<(&'a Token, Cursor<'a>)> {
+    let (first, rest) = cursor.tokens.split_first()?;
+    Some((first, Cursor { tokens: rest }))
+}
+
+fn unexpected(tok: &Token, expected: &str) -> ParseError {
+    ParseError {
+        expected: expected.to_string(),
+        found: Some(tok.clone()),
+        loc: tok.loc(),
+    }
+}
+
+fn unexpected_eof<'a>(cursor: Cursor<'a>, expected: &str) -> ParseError {
+    ParseError {
+        expected: expected.to_string(),
+        found: None,
+        loc: cursor.loc(),
+    }
+}
+
+fn expect<'a>(cursor: Cursor<'a>, is_match: fn(&Token) -> bool, expected: &str) -> Result<Cursor<'a>, ParseError> {
+    match bump(cursor) {
+        Some((tok, next)) if is_match(tok) => Ok(next),
+        Some((tok, _)) => Err(unexpected(tok, expected)),
+        None => Err(unexpected_eof(cursor, expected)),
+    }
+}
+
+fn expect_equal<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::Equal(_)), "`=`")
+}
+
+fn expect_colon<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::Colon(_)), "`:`")
+}
+
+fn expect_semicolon<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::Semicolon(_)), "`;`")
+}
+
+fn expect_open_paren<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::OpenParen(_)), "`(`")
+}
+
+fn expect_close_paren<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::CloseParen(_)), "`)`")
+}
+
+fn expect_open_brace<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::OpenBrace(_)), "`{`")
+}
+
+fn expect_close_brace<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::CloseBrace(_)), "`}`")
+}
+
+fn expect_open_angle<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::OpenAngleBracket(_)), "`<`")
+}
+
+fn expect_close_angle<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::CloseAngleBracket(_)), "`>`")
+}
+
+fn expect_comma<'a>(cursor: Cursor<'a>) -> Result<Cursor<'a>, ParseError> {
+    expect(cursor, |t| matches!(t, Token::Comma(_)), "`,`")
+}
+
+fn parse_identifier<'a>(cursor: Cursor<'a>, expected: &str) -> ParseResult<'a, String> {
+    match bump(cursor) {
+        Some((Token::Identifier(name, _), next)) => Ok((name.clone(), next)),
+        Some((tok, _)) => Err(unexpected(tok, expected)),
+        None => Err(unexpected_eof(cursor, expected)),
+    }
+}
+
+/// Parses a full Djinni source file into its top-level declarations.
+pub fn parse(tokens: &[Token]) -> Result<Vec<Decl>, ParseError> {
+    let mut cursor = Cursor::new(tokens);
+    let mut decls = Vec::new();
+
+    while let Some(tok) = cursor.peek() {
+        if let Token::Comment(_, _) = tok {
+            cursor = bump(cursor).unwrap().1;
+            continue;
+        }
+        let (decl, next) = parse_decl(cursor)?;
+        decls.push(decl);
+        cursor = next;
+    }
+
+    Ok(decls)
+}
+
+fn parse_decl<'a>(cursor: Cursor<'a>) -> ParseResult<'a, Decl> {
+    match cursor.peek() {
+        Some(Token::DirectiveImport(_)) | Some(Token::DirectiveExtern(_)) => parse_directive(cursor),
+        _ => {
+            let loc = cursor.loc();
+            let (name, next) = parse_identifier(cursor, "declaration name")?;
+            let next = expect_equal(next)?;
+            match next.peek() {
+                Some(Token::KeywordEnum(_)) => {
+                    let (en, next) = parse_enum(name, loc, next)?;
+                    Ok((Decl::Enum(en), next))
+                }
+                Some(Token::KeywordRecord(_)) => {
+                    let (rec, next) = parse_record(name, loc, next)?;
+                    Ok((Decl::Record(rec), next))
+                }
+                Some(Token::KeywordInterface(_)) => {
+                    let (iface, next) = parse_interface(name, loc, next)?;
+                    Ok((Decl::Interface(iface), next))
+                }
+                Some(tok) => Err(unexpected(tok, "`enum`, `record`, or `interface`")),
+                None => Err(unexpected_eof(next, "`enum`, `record`, or `interface`")),
+            }
+        }
+    }
+}
+
+fn parse_directive<'a>(cursor: Cursor<'a>) -> ParseResult<'a, Decl> {
+    let (tok, next) = bump(cursor).ok_or_else(|| unexpected_eof(cursor, "`@import` or `@extern`"))?;
+    match tok {
+        Token::DirectiveImport(loc) => {
+            let loc = *loc;
+            let (path, next) = parse_directive_path(next)?;
+            Ok((Decl::Directive(Directive::Import(path, loc)), next))
+        }
+        Token::DirectiveExtern(loc) => {
+            let loc = *loc;
+            let (path, next) = parse_directive_path(next)?;
+            Ok((Decl::Directive(Directive::Extern(path, loc)), next))
+        }
+        _ => Err(unexpected(tok, "`@import` or `@extern`")),
+    }
+}
+
+fn parse_directive_path<'a>(cursor: Cursor<'a>) -> ParseResult<'a, String> {
+    match bump(cursor) {
+        Some((Token::StringLiteral(path, _), next)) => Ok((path.clone(), next)),
+        Some((tok, _)) => Err(unexpected(tok, "directive path")),
+        None => Err(unexpected_eof(cursor, "directive path")),
+    }
+}
+
+/// Advances past any `Comment` tokens, the same way `parse`'s top-level
+/// loop does, so doc-comments preceding a member don't trip up its parse.
+fn skip_comments(cursor: Cursor<'_>) -> Cursor<'_> {
+    let mut cursor = cursor;
+    while matches!(cursor.peek(), Some(Token::Comment(_, _))) {
+        cursor = bump(cursor).unwrap().1;
+    }
+    cursor
+}
+
+fn parse_langs<'a>(cursor: Cursor<'a>) -> (Vec<Lang>, Cursor<'a>) {
+    let mut langs = Vec::new();
+    let mut cursor = cursor;
+    loop {
+        let lang = match cursor.peek() {
+            Some(Token::LangCpp(_)) => Lang::Cpp,
+            Some(Token::LangJava(_)) => Lang::Java,
+            Some(Token::LangObjC(_)) => Lang::ObjC,
+            _ => break,
+        };
+        langs.push(lang);
+        cursor = bump(cursor).unwrap().1;
+    }
+    (langs, cursor)
+}
+
+fn parse_enum<'a>(name: String, loc: Loc, cursor: Cursor<'a>) -> ParseResult<'a, Enum> {
+    let (_, next) = bump(cursor).unwrap(); // `enum`, guaranteed by the caller's peek
+    let mut cursor = expect_open_brace(next)?;
+
+    let mut values = Vec::new();
+    loop {
+        cursor = skip_comments(cursor);
+        if matches!(cursor.peek(), Some(Token::CloseBrace(_))) {
+            break;
+        }
+        let (value, next) = parse_identifier(cursor, "enum value")?;
+        let next = expect_semicolon(next)?;
+        values.push(value);
+        cursor = next;
+    }
+    let cursor = expect_close_brace(cursor)?;
+
+    Ok((Enum { name, values, loc }, cursor))
+}
+
+fn parse_record<'a>(name: String, loc: Loc, cursor: Cursor<'a>) -> ParseResult<'a, Record> {
+    let (_, next) = bump(cursor).unwrap(); // `record`, guaranteed by the caller's peek
+    let (langs, next) = parse_langs(next);
+    let mut cursor = expect_open_brace(next)?;
+
+    let mut fields = Vec::new();
+    loop {
+        cursor = skip_comments(cursor);
+        if matches!(cursor.peek(), Some(Token::CloseBrace(_))) {
+            break;
+        }
+        let (field, next) = parse_field(cursor)?;
+        fields.push(field);
+        cursor = next;
+    }
+    let mut cursor = expect_close_brace(cursor)?;
+
+    let mut deriving = Vec::new();
+    if matches!(cursor.peek(), Some(Token::KeywordDeriving(_))) {
+        let (names, next) = parse_deriving(cursor)?;
+        deriving = names;
+        cursor = next;
+    }
+
+    Ok((
+        Record {
+            name,
+            langs,
+            fields,
+            deriving,
+            loc,
+        },
+        cursor,
+    ))
+}
+
+fn parse_field<'a>(cursor: Cursor<'a>) -> ParseResult<'a, Field> {
+    let loc = cursor.loc();
+    let (name, next) = parse_identifier(cursor, "field name")?;
+    let next = expect_colon(next)?;
+    let (type_ref, next) = parse_type_ref(next)?;
+    let next = expect_semicolon(next)?;
+    Ok((Field { name, type_ref, loc }, next))
+}
+
+fn parse_deriving<'a>(cursor: Cursor<'a>) -> ParseResult<'a, Vec<String>> {
+    let (_, next) = bump(cursor).unwrap(); // `deriving`, guaranteed by the caller's peek
+    let next = expect_open_paren(next)?;
+
+    let mut names = Vec::new();
+    let mut cursor = next;
+    loop {
+        let (name, next) = parse_identifier(cursor, "deriving trait")?;
+        names.push(name);
+        cursor = next;
+        if matches!(cursor.peek(), Some(Token::Comma(_))) {
+            cursor = bump(cursor).unwrap().1;
+        } else {
+            break;
+        }
+    }
+
+    let cursor = expect_close_paren(cursor)?;
+    Ok((names, cursor))
+}
+
+fn parse_interface<'a>(name: String, loc: Loc, cursor: Cursor<'a>) -> ParseResult<'a, Interface> {
+    let (_, next) = bump(cursor).unwrap(); // `interface`, guaranteed by the caller's peek
+    let (langs, next) = parse_langs(next);
+    let mut cursor = expect_open_brace(next)?;
+
+    let mut methods = Vec::new();
+    loop {
+        cursor = skip_comments(cursor);
+        if matches!(cursor.peek(), Some(Token::CloseBrace(_))) {
+            break;
+        }
+        let (method, next) = parse_method(cursor)?;
+        methods.push(method);
+        cursor = next;
+    }
+    let cursor = expect_close_brace(cursor)?;
+
+    Ok((
+        Interface {
+            name,
+            langs,
+            methods,
+            loc,
+        },
+        cursor,
+    ))
+}
+
+fn parse_method<'a>(cursor: Cursor<'a>) -> ParseResult<'a, Method> {
+    let loc = cursor.loc();
+    let (is_static, cursor) = match cursor.peek() {
+        Some(Token::KeywordStatic(_)) => (true, bump(cursor).unwrap().1),
+        _ => (false, cursor),
+    };
+
+    let (name, cursor) = parse_identifier(cursor, "method name")?;
+    let cursor = expect_open_paren(cursor)?;
+    let (params, cursor) = parse_params(cursor)?;
+    let cursor = expect_close_paren(cursor)?;
+
+    let (return_type, cursor) = if matches!(cursor.peek(), Some(Token::Colon(_))) {
+        let cursor = bump(cursor).unwrap().1;
+        let (type_ref, cursor) = parse_type_ref(cursor)?;
+        (Some(type_ref), cursor)
+    } else {
+        (None, cursor)
+    };
+
+    let cursor = expect_semicolon(cursor)?;
+
+    Ok((
+        Method {
+            name,
+            is_static,
+            params,
+            return_type,
+            loc,
+        },
+        cursor,
+    ))
+}
+
+fn parse_params<'a>(cursor: Cursor<'a>) -> ParseResult<'a, Vec<Param>> {
+    if matches!(cursor.peek(), Some(Token::CloseParen(_))) {
+        return Ok((Vec::new(), cursor));
+    }
+
+    let mut params = Vec::new();
+    let mut cursor = cursor;
+    loop {
+        let loc = cursor.loc();
+        let (name, next) = parse_identifier(cursor, "parameter name")?;
+        let next = expect_colon(next)?;
+        let (type_ref, next) = parse_type_ref(next)?;
+        params.push(Param { name, type_ref, loc });
+        cursor = next;
+
+        if matches!(cursor.peek(), Some(Token::Comma(_))) {
+            cursor = bump(cursor).unwrap().1;
+        } else {
+            break;
+        }
+    }
+
+    Ok((params, cursor))
+}
+
+fn parse_type_ref<'a>(cursor: Cursor<'a>) -> ParseResult<'a, TypeRef> {
+    let (tok, next) = bump(cursor).ok_or_else(|| unexpected_eof(cursor, "type"))?;
+    match tok {
+        Token::KeywordList(_) => parse_generic_type(next, TypeRef::List),
+        Token::KeywordSet(_) => parse_generic_type(next, TypeRef::Set),
+        Token::KeywordOptional(_) => parse_generic_type(next, TypeRef::Optional),
+        Token::KeywordMap(_) => parse_map_type(next),
+        Token::KeywordBool(_) => Ok((TypeRef::Simple("bool".to_string()), next)),
+        Token::KeywordI8(_) => Ok((TypeRef::Simple("i8".to_string()), next)),
+        Token::KeywordI16(_) => Ok((TypeRef::Simple("i16".to_string()), next)),
+        Token::KeywordI32(_) => Ok((TypeRef::Simple("i32".to_string()), next)),
+        Token::KeywordI64(_) => Ok((TypeRef::Simple("i64".to_string()), next)),
+        Token::KeywordF32(_) => Ok((TypeRef::Simple("f32".to_string()), next)),
+        Token::KeywordF64(_) => Ok((TypeRef::Simple("f64".to_string()), next)),
+        Token::KeywordString(_) => Ok((TypeRef::Simple("string".to_string()), next)),
+        Token::KeywordBinary(_) => Ok((TypeRef::Simple("binary".to_string()), next)),
+        Token::KeywordDate(_) => Ok((TypeRef::Simple("date".to_string()), next)),
+        Token::Identifier(name, _) => Ok((TypeRef::Simple(name.clone()), next)),
+        _ => Err(unexpected(tok, "type")),
+    }
+}
+
+fn parse_generic_type<'a>(cursor: Cursor<'a>, wrap: fn(Box<TypeRef>) -> TypeRef) -> ParseResult<'a, TypeRef> {
+    let next = expect_open_angle(cursor)?;
+    let (inner, next) = parse_type_ref(next)?;
+    let next = expect_close_angle(next)?;
+    Ok((wrap(Box::new(inner)), next))
+}
+
+fn parse_map_type<'a>(cursor: Cursor<'a>) -> ParseResult<'a, TypeRef> {
+    let next = expect_open_angle(cursor)?;
+    let (key, next) = parse_type_ref(next)?;
+    let next = expect_comma(next)?;
+    let (value, next) = parse_type_ref(next)?;
+    let next = expect_close_angle(next)?;
+    Ok((TypeRef::Map(Box::new(key), Box::new(value)), next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::tokenize;
+
+    #[test]
+    fn test_parse_interface() {
+        let input = r#"
+            accounts_API = interface +c {
+                static create(): accounts_API;
+            }
+        "#;
+
+        let tokens = tokenize(input).unwrap();
+        let decls = parse(&tokens).unwrap();
+
+        assert_eq!(decls.len(), 1);
+        match &decls[0] {
+            Decl::Interface(iface) => {
+                assert_eq!(iface.name, "accounts_API");
+                assert_eq!(iface.langs, vec![Lang::Cpp]);
+                assert_eq!(iface.methods.len(), 1);
+                assert_eq!(iface.methods[0].name, "create");
+                assert!(iface.methods[0].is_static);
+                assert!(iface.methods[0].params.is_empty());
+                assert_eq!(
+                    iface.methods[0].return_type,
+                    Some(TypeRef::Simple("accounts_API".to_string()))
+                );
+            }
+            other => panic!("expected interface, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_record_with_deriving() {
+        let input = r#"
+            user_profile = record {
+                id: string;
+                tags: list<string>;
+                metadata: optional<map<string, binary>>;
+            } deriving(eq, ord)
+        "#;
+
+        let tokens = tokenize(input).unwrap();
+        let decls = parse(&tokens).unwrap();
+
+        assert_eq!(decls.len(), 1);
+        match &decls[0] {
+            Decl::Record(rec) => {
+                assert_eq!(rec.name, "user_profile");
+                assert_eq!(rec.fields.len(), 3);
+                assert_eq!(rec.fields[0].type_ref, TypeRef::Simple("string".to_string()));
+                assert_eq!(
+                    rec.fields[1].type_ref,
+                    TypeRef::List(Box::new(TypeRef::Simple("string".to_string())))
+                );
+                assert_eq!(
+                    rec.fields[2].type_ref,
+                    TypeRef::Optional(Box::new(TypeRef::Map(
+                        Box::new(TypeRef::Simple("string".to_string())),
+                        Box::new(TypeRef::Simple("binary".to_string()))
+                    )))
+                );
+                assert_eq!(rec.deriving, vec!["eq".to_string(), "ord".to_string()]);
+            }
+            other => panic!("expected record, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_enum() {
+        let input = r#"
+            connection_state = enum {
+                connected;
+                disconnected;
+            }
+        "#;
+
+        let tokens = tokenize(input).unwrap();
+        let decls = parse(&tokens).unwrap();
+
+        assert_eq!(decls.len(), 1);
+        match &decls[0] {
+            Decl::Enum(en) => {
+                assert_eq!(en.name, "connection_state");
+                assert_eq!(en.values, vec!["connected".to_string(), "disconnected".to_string()]);
+            }
+            other => panic!("expected enum, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_skips_comments_before_members() {
+        let input = r#"
+            accounts_API = interface +c {
+                # creates an account
+                static create(): accounts_API;
+            }
+            user_profile = record {
+                # the user's id
+                id: string;
+            }
+            connection_state = enum {
+                # currently connected
+                connected;
+            }
+        "#;
+
+        let tokens = tokenize(input).unwrap();
+        let decls = parse(&tokens).unwrap();
+
+        assert_eq!(decls.len(), 3);
+        match &decls[0] {
+            Decl::Interface(iface) => assert_eq!(iface.methods[0].name, "create"),
+            other => panic!("expected interface, got {:?}", other),
+        }
+        match &decls[1] {
+            Decl::Record(rec) => assert_eq!(rec.fields[0].name, "id"),
+            other => panic!("expected record, got {:?}", other),
+        }
+        match &decls[2] {
+            Decl::Enum(en) => assert_eq!(en.values[0], "connected"),
+            other => panic!("expected enum, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_decl_loc_is_name_token() {
+        let input = "connection_state = enum {\n    connected;\n}";
+        let tokens = tokenize(input).unwrap();
+        let decls = parse(&tokens).unwrap();
+
+        match &decls[0] {
+            Decl::Enum(en) => assert_eq!(en.loc, Loc::default()),
+            other => panic!("expected enum, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_import_directive() {
+        let input = r#"@import "common.djinni""#;
+        let tokens = tokenize(input).unwrap();
+        let decls = parse(&tokens).unwrap();
+
+        assert_eq!(decls.len(), 1);
+        assert_eq!(
+            decls[0],
+            Decl::Directive(Directive::Import(
+                "common.djinni".to_string(),
+                Loc::default()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_error_reports_loc() {
+        let input = "broken = enum { first }";
+        let tokens = tokenize(input).unwrap();
+        let err = parse(&tokens).unwrap_err();
+
+        assert_eq!(err.expected, "`;`");
+        assert_eq!(err.found, Some(Token::CloseBrace(Loc::default())));
+        assert_eq!(err.loc, tokens[5].loc());
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 pub const TAB_SIZE: usize = 4;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -6,13 +8,15 @@ pub struct Loc {
     column: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Token {
     Identifier(String, Loc),
+    StringLiteral(String, Loc), // "..."
     Comment(String, Loc),   // # comment
     Equal(Loc),             // =
     Colon(Loc),             // :
     Semicolon(Loc),         // ;
+    Comma(Loc),             // ,
     OpenParen(Loc),         // (
     CloseParen(Loc),        // )
     OpenBrace(Loc),         // {
@@ -53,10 +57,12 @@ impl PartialEq for Token {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Token::Identifier(a, _), Token::Identifier(b, _)) => a == b,
+            (Token::StringLiteral(a, _), Token::StringLiteral(b, _)) => a == b,
             (Token::Comment(a, _), Token::Comment(b, _)) => a == b,
             (Token::Equal(_), Token::Equal(_)) => true,
             (Token::Colon(_), Token::Colon(_)) => true,
             (Token::Semicolon(_), Token::Semicolon(_)) => true,
+            (Token::Comma(_), Token::Comma(_)) => true,
             (Token::OpenParen(_), Token::OpenParen(_)) => true,
             (Token::CloseParen(_), Token::CloseParen(_)) => true,
             (Token::OpenBrace(_), Token::OpenBrace(_)) => true,
@@ -97,10 +103,252 @@ pub enum TokenizeError {
     InvalidChar(Loc),
     UnknownDirective(Loc),
     UnknownLanguage(Loc),
+    UnterminatedString(Loc),
+    InvalidEscape(Loc),
 }
 
 pub type TokenizeResult = Result<Vec<Token>, TokenizeError>;
 
+impl Token {
+    /// The source location this token was scanned from.
+    pub fn loc(&self) -> Loc {
+        match self {
+            Token::Identifier(_, loc) => *loc,
+            Token::StringLiteral(_, loc) => *loc,
+            Token::Comment(_, loc) => *loc,
+            Token::Equal(loc) => *loc,
+            Token::Colon(loc) => *loc,
+            Token::Semicolon(loc) => *loc,
+            Token::Comma(loc) => *loc,
+            Token::OpenParen(loc) => *loc,
+            Token::CloseParen(loc) => *loc,
+            Token::OpenBrace(loc) => *loc,
+            Token::CloseBrace(loc) => *loc,
+            Token::OpenAngleBracket(loc) => *loc,
+            Token::CloseAngleBracket(loc) => *loc,
+            Token::DirectiveExtern(loc) => *loc,
+            Token::DirectiveImport(loc) => *loc,
+            Token::LangCpp(loc) => *loc,
+            Token::LangJava(loc) => *loc,
+            Token::LangObjC(loc) => *loc,
+            Token::KeywordEnum(loc) => *loc,
+            Token::KeywordRecord(loc) => *loc,
+            Token::KeywordInterface(loc) => *loc,
+            Token::KeywordStatic(loc) => *loc,
+            Token::KeywordDeriving(loc) => *loc,
+            Token::KeywordBool(loc) => *loc,
+            Token::KeywordI8(loc) => *loc,
+            Token::KeywordI16(loc) => *loc,
+            Token::KeywordI32(loc) => *loc,
+            Token::KeywordI64(loc) => *loc,
+            Token::KeywordF32(loc) => *loc,
+            Token::KeywordF64(loc) => *loc,
+            Token::KeywordString(loc) => *loc,
+            Token::KeywordBinary(loc) => *loc,
+            Token::KeywordDate(loc) => *loc,
+            Token::KeywordList(loc) => *loc,
+            Token::KeywordSet(loc) => *loc,
+            Token::KeywordMap(loc) => *loc,
+            Token::KeywordOptional(loc) => *loc,
+        }
+    }
+}
+
+/// Renders the exact lexeme a `Token` was scanned from, so that
+/// `format(tokenize(src).unwrap())` round-trips `src` up to whitespace.
+/// The one exception is a `\uXXXX` escape in a string literal, which is
+/// rendered as the decoded character itself.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Identifier(name, _) => write!(f, "{}", name),
+            Token::StringLiteral(value, _) => write!(f, "\"{}\"", escape_string_literal(value)),
+            Token::Comment(text, _) => {
+                let lines: Vec<String> = text.split('\n').map(|line| format!("#{}", line)).collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            Token::Equal(_) => write!(f, "="),
+            Token::Colon(_) => write!(f, ":"),
+            Token::Semicolon(_) => write!(f, ";"),
+            Token::Comma(_) => write!(f, ","),
+            Token::OpenParen(_) => write!(f, "("),
+            Token::CloseParen(_) => write!(f, ")"),
+            Token::OpenBrace(_) => write!(f, "{{"),
+            Token::CloseBrace(_) => write!(f, "}}"),
+            Token::OpenAngleBracket(_) => write!(f, "<"),
+            Token::CloseAngleBracket(_) => write!(f, ">"),
+            Token::DirectiveExtern(_) => write!(f, "@extern"),
+            Token::DirectiveImport(_) => write!(f, "@import"),
+            Token::LangCpp(_) => write!(f, "+c"),
+            Token::LangJava(_) => write!(f, "+j"),
+            Token::LangObjC(_) => write!(f, "+o"),
+            Token::KeywordEnum(_) => write!(f, "enum"),
+            Token::KeywordRecord(_) => write!(f, "record"),
+            Token::KeywordInterface(_) => write!(f, "interface"),
+            Token::KeywordStatic(_) => write!(f, "static"),
+            Token::KeywordDeriving(_) => write!(f, "deriving"),
+            Token::KeywordBool(_) => write!(f, "bool"),
+            Token::KeywordI8(_) => write!(f, "i8"),
+            Token::KeywordI16(_) => write!(f, "i16"),
+            Token::KeywordI32(_) => write!(f, "i32"),
+            Token::KeywordI64(_) => write!(f, "i64"),
+            Token::KeywordF32(_) => write!(f, "f32"),
+            Token::KeywordF64(_) => write!(f, "f64"),
+            Token::KeywordString(_) => write!(f, "string"),
+            Token::KeywordBinary(_) => write!(f, "binary"),
+            Token::KeywordDate(_) => write!(f, "date"),
+            Token::KeywordList(_) => write!(f, "list"),
+            Token::KeywordSet(_) => write!(f, "set"),
+            Token::KeywordMap(_) => write!(f, "map"),
+            Token::KeywordOptional(_) => write!(f, "optional"),
+        }
+    }
+}
+
+/// Reverses the escape handling done while scanning a `"..."` literal in
+/// `tokenize`, so `Display` can re-quote a `StringLiteral`'s value.
+fn escape_string_literal(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Re-assembles a token stream into source text: a newline follows every
+/// `;` and `{`, and lines inside braces are indented by `TAB_SIZE` spaces
+/// per nesting level. Gives callers a deterministic pretty-printer and a
+/// cheap way to assert that `tokenize` round-trips.
+pub fn format(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    let mut depth: usize = 0;
+    let mut at_line_start = true;
+    let mut suppress_space = true;
+
+    for token in tokens {
+        if matches!(token, Token::CloseBrace(_)) {
+            depth = depth.saturating_sub(1);
+        }
+
+        let hugs_left = matches!(
+            token,
+            Token::Semicolon(_)
+                | Token::Colon(_)
+                | Token::Comma(_)
+                | Token::CloseParen(_)
+                | Token::CloseAngleBracket(_)
+                | Token::OpenParen(_)
+                | Token::OpenAngleBracket(_)
+        );
+
+        let indent = " ".repeat(depth * TAB_SIZE);
+        if at_line_start {
+            out.push_str(&indent);
+            at_line_start = false;
+        } else if !suppress_space && !hugs_left {
+            out.push(' ');
+        }
+
+        if let Token::Comment(text, _) = token {
+            let lines: Vec<String> = text.split('\n').map(|line| format!("#{}", line)).collect();
+            out.push_str(&lines.join(&format!("\n{}", indent)));
+        } else {
+            out.push_str(&token.to_string());
+        }
+        suppress_space = matches!(token, Token::OpenParen(_) | Token::OpenAngleBracket(_));
+
+        match token {
+            Token::OpenBrace(_) => {
+                depth += 1;
+                out.push('\n');
+                at_line_start = true;
+            }
+            Token::Semicolon(_) => {
+                out.push('\n');
+                at_line_start = true;
+            }
+            Token::Comment(_, _) => {
+                out.push('\n');
+                at_line_start = true;
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+const KEYWORD_TABLE_SIZE: usize = 19;
+
+/// Per-byte associated values used by `hash_keyword` to place each keyword
+/// into its own slot of `KEYWORDS_LIST`, gperf-style: the hash of a word is
+/// its length plus the associated values of its first and last bytes.
+static ASSO_VALUES: [u8; 128] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+    0, 0, 16, 0, 2, 0, 17, 0, 6, 0, 0, 0, 0, 0, 0, 0, // '2' '4' '6' '8'
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+    0, 0, 10, 2, 12, 14, 10, 6, 0, 1, 0, 0, 3, 5, 0, 2, // 'b' 'c' 'd' 'e' 'f' 'g' 'i' 'l' 'm' 'o'
+    11, 0, 17, 0, 11, 0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, // 'p' 'r' 's' 't' 'y'
+];
+
+fn hash_keyword(word: &str) -> usize {
+    let bytes = word.as_bytes();
+    let asso = |byte: u8| ASSO_VALUES.get(byte as usize).copied().unwrap_or(0) as usize;
+    (word.len() + asso(bytes[0]) + asso(bytes[bytes.len() - 1])) % KEYWORD_TABLE_SIZE
+}
+
+/// A keyword's lexeme paired with the constructor for its `Token` variant.
+type KeywordEntry = (&'static str, fn(Loc) -> Token);
+
+/// One slot per keyword, indexed by `hash_keyword`. Built so that every
+/// Djinni keyword lands in its own slot with no collisions.
+static KEYWORDS_LIST: [KeywordEntry; KEYWORD_TABLE_SIZE] = [
+    ("map", Token::KeywordMap),
+    ("i32", Token::KeywordI32),
+    ("i16", Token::KeywordI16),
+    ("binary", Token::KeywordBinary),
+    ("enum", Token::KeywordEnum),
+    ("interface", Token::KeywordInterface),
+    ("i64", Token::KeywordI64),
+    ("deriving", Token::KeywordDeriving),
+    ("static", Token::KeywordStatic),
+    ("i8", Token::KeywordI8),
+    ("f32", Token::KeywordF32),
+    ("date", Token::KeywordDate),
+    ("string", Token::KeywordString),
+    ("optional", Token::KeywordOptional),
+    ("set", Token::KeywordSet),
+    ("f64", Token::KeywordF64),
+    ("record", Token::KeywordRecord),
+    ("bool", Token::KeywordBool),
+    ("list", Token::KeywordList),
+];
+
+/// Classifies `word` as a keyword token, falling back to `Token::Identifier`
+/// on any mismatch. One hash lookup plus a cheap fail-early compare (length,
+/// then first byte, then the full string) replaces the linear keyword match.
+pub fn lookup_keyword(word: &str, loc: Loc) -> Token {
+    if word.is_empty() {
+        return Token::Identifier(word.to_string(), loc);
+    }
+
+    let (candidate, make_token) = KEYWORDS_LIST[hash_keyword(word)];
+    if candidate.len() == word.len() && candidate.as_bytes()[0] == word.as_bytes()[0] && candidate == word {
+        make_token(loc)
+    } else {
+        Token::Identifier(word.to_string(), loc)
+    }
+}
+
 pub fn tokenize(input: &str) -> TokenizeResult {
     let mut iter = input.chars().peekable();
     let mut loc = Loc::default();
@@ -137,6 +385,67 @@ pub fn tokenize(input: &str) -> TokenizeResult {
                 loc.column = 0;
                 continue;
             }
+            '"' => {
+                let start_loc = loc;
+                let mut value = String::new();
+                let mut len = 1; // the opening quote
+                let mut closed = false;
+
+                while let Some(c) = iter.next() {
+                    len += 1;
+                    match c {
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        '\n' => break,
+                        '\\' => match iter.next() {
+                            Some('"') => {
+                                value.push('"');
+                                len += 1;
+                            }
+                            Some('\\') => {
+                                value.push('\\');
+                                len += 1;
+                            }
+                            Some('n') => {
+                                value.push('\n');
+                                len += 1;
+                            }
+                            Some('t') => {
+                                value.push('\t');
+                                len += 1;
+                            }
+                            Some('u') => {
+                                len += 1;
+                                let mut hex = String::new();
+                                for _ in 0..4 {
+                                    match iter.next() {
+                                        Some(h) if h.is_ascii_hexdigit() => {
+                                            hex.push(h);
+                                            len += 1;
+                                        }
+                                        _ => return Err(TokenizeError::UnterminatedString(start_loc)),
+                                    }
+                                }
+                                let code = u32::from_str_radix(&hex, 16).unwrap();
+                                value.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                            }
+                            Some(_) => return Err(TokenizeError::InvalidEscape(start_loc)),
+                            None => return Err(TokenizeError::UnterminatedString(start_loc)),
+                        },
+                        _ => value.push(c),
+                    }
+                }
+
+                if !closed {
+                    return Err(TokenizeError::UnterminatedString(start_loc));
+                }
+
+                tokens.push(Token::StringLiteral(value, start_loc));
+                loc.column += len;
+                continue;
+            }
             '@' => {
                 // read a word after '@'
                 let mut word = String::new();
@@ -160,6 +469,7 @@ pub fn tokenize(input: &str) -> TokenizeResult {
             '=' => tokens.push(Token::Equal(loc)),
             ':' => tokens.push(Token::Colon(loc)),
             ';' => tokens.push(Token::Semicolon(loc)),
+            ',' => tokens.push(Token::Comma(loc)),
             '(' => tokens.push(Token::OpenParen(loc)),
             ')' => tokens.push(Token::CloseParen(loc)),
             '{' => tokens.push(Token::OpenBrace(loc)),
@@ -196,28 +506,7 @@ pub fn tokenize(input: &str) -> TokenizeResult {
                 }
 
                 let word_len = word.len();
-                match word.as_str() {
-                    "enum" => tokens.push(Token::KeywordEnum(loc)),
-                    "record" => tokens.push(Token::KeywordRecord(loc)),
-                    "interface" => tokens.push(Token::KeywordInterface(loc)),
-                    "static" => tokens.push(Token::KeywordStatic(loc)),
-                    "deriving" => tokens.push(Token::KeywordDeriving(loc)),
-                    "bool" => tokens.push(Token::KeywordBool(loc)),
-                    "i8" => tokens.push(Token::KeywordI8(loc)),
-                    "i16" => tokens.push(Token::KeywordI16(loc)),
-                    "i32" => tokens.push(Token::KeywordI32(loc)),
-                    "i64" => tokens.push(Token::KeywordI64(loc)),
-                    "f32" => tokens.push(Token::KeywordF32(loc)),
-                    "f64" => tokens.push(Token::KeywordF64(loc)),
-                    "string" => tokens.push(Token::KeywordString(loc)),
-                    "binary" => tokens.push(Token::KeywordBinary(loc)),
-                    "date" => tokens.push(Token::KeywordDate(loc)),
-                    "list" => tokens.push(Token::KeywordList(loc)),
-                    "set" => tokens.push(Token::KeywordSet(loc)),
-                    "map" => tokens.push(Token::KeywordMap(loc)),
-                    "optional" => tokens.push(Token::KeywordOptional(loc)),
-                    _ => tokens.push(Token::Identifier(word, loc)),
-                }
+                tokens.push(lookup_keyword(&word, loc));
                 loc.column += word_len;
                 continue;
             }
@@ -236,6 +525,51 @@ pub fn tokenize(input: &str) -> TokenizeResult {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_lookup_keyword_matches_every_keyword() {
+        let keywords: &[(&str, Token)] = &[
+            ("enum", Token::KeywordEnum(Loc::default())),
+            ("record", Token::KeywordRecord(Loc::default())),
+            ("interface", Token::KeywordInterface(Loc::default())),
+            ("static", Token::KeywordStatic(Loc::default())),
+            ("deriving", Token::KeywordDeriving(Loc::default())),
+            ("bool", Token::KeywordBool(Loc::default())),
+            ("i8", Token::KeywordI8(Loc::default())),
+            ("i16", Token::KeywordI16(Loc::default())),
+            ("i32", Token::KeywordI32(Loc::default())),
+            ("i64", Token::KeywordI64(Loc::default())),
+            ("f32", Token::KeywordF32(Loc::default())),
+            ("f64", Token::KeywordF64(Loc::default())),
+            ("string", Token::KeywordString(Loc::default())),
+            ("binary", Token::KeywordBinary(Loc::default())),
+            ("date", Token::KeywordDate(Loc::default())),
+            ("list", Token::KeywordList(Loc::default())),
+            ("set", Token::KeywordSet(Loc::default())),
+            ("map", Token::KeywordMap(Loc::default())),
+            ("optional", Token::KeywordOptional(Loc::default())),
+        ];
+
+        for (word, expected) in keywords {
+            assert_eq!(lookup_keyword(word, Loc::default()), *expected);
+        }
+    }
+
+    #[test]
+    fn test_lookup_keyword_falls_back_to_identifier() {
+        assert_eq!(
+            lookup_keyword("maps", Loc::default()),
+            Token::Identifier("maps".to_string(), Loc::default())
+        );
+        assert_eq!(
+            lookup_keyword("seti", Loc::default()),
+            Token::Identifier("seti".to_string(), Loc::default())
+        );
+        assert_eq!(
+            lookup_keyword("accounts_API", Loc::default()),
+            Token::Identifier("accounts_API".to_string(), Loc::default())
+        );
+    }
+
     macro_rules! token_eq {
         ($expr:expr, None) => {
             assert_eq!($expr, None);
@@ -400,4 +734,138 @@ mod tests {
             Err(TokenizeError::UnknownDirective(Loc { line: 0, column: 0 }))
         );
     }
+
+    #[test]
+    fn test_tokenize_string_literal() {
+        let input = r#"@import "common.djinni""#;
+
+        let mut tokens = tokenize(input).unwrap().into_iter();
+
+        token_eq!(tokens.next(), Some(DirectiveImport));
+        token_eq!(tokens.next(), Some(StringLiteral("common.djinni")));
+        token_eq!(tokens.next(), None);
+    }
+
+    #[test]
+    fn test_tokenize_string_literal_escapes() {
+        let input = r#""line1\nline2\ttab\\\"quoted\"A""#;
+
+        let mut tokens = tokenize(input).unwrap().into_iter();
+
+        token_eq!(
+            tokens.next(),
+            Some(StringLiteral("line1\nline2\ttab\\\"quoted\"A"))
+        );
+        token_eq!(tokens.next(), None);
+    }
+
+    #[test]
+    fn test_tokenize_string_literal_non_ascii() {
+        let input = r#""café""#;
+
+        let mut tokens = tokenize(input).unwrap().into_iter();
+
+        token_eq!(tokens.next(), Some(StringLiteral("caf\u{e9}")));
+        token_eq!(tokens.next(), None);
+    }
+
+    #[test]
+    fn test_tokenize_string_literal_unicode_escape() {
+        let input = "\"caf\\u00e9\"";
+
+        let mut tokens = tokenize(input).unwrap().into_iter();
+
+        token_eq!(tokens.next(), Some(StringLiteral("caf\u{e9}")));
+        token_eq!(tokens.next(), None);
+    }
+
+    #[test]
+    fn test_tokenize_error_unterminated_string_eof() {
+        let input = r#""common.djinni"#;
+
+        assert_eq!(
+            tokenize(input),
+            Err(TokenizeError::UnterminatedString(Loc { line: 0, column: 0 }))
+        );
+    }
+
+    #[test]
+    fn test_tokenize_error_unterminated_string_newline() {
+        let input = "\"common\ndjinni\"";
+
+        assert_eq!(
+            tokenize(input),
+            Err(TokenizeError::UnterminatedString(Loc { line: 0, column: 0 }))
+        );
+    }
+
+    #[test]
+    fn test_tokenize_error_invalid_escape() {
+        let input = r#""bad\zescape""#;
+
+        assert_eq!(
+            tokenize(input),
+            Err(TokenizeError::InvalidEscape(Loc { line: 0, column: 0 }))
+        );
+    }
+
+    #[test]
+    fn test_display_renders_lexemes() {
+        assert_eq!(Token::Equal(Loc::default()).to_string(), "=");
+        assert_eq!(Token::KeywordOptional(Loc::default()).to_string(), "optional");
+        assert_eq!(Token::DirectiveImport(Loc::default()).to_string(), "@import");
+        assert_eq!(Token::LangCpp(Loc::default()).to_string(), "+c");
+        assert_eq!(
+            Token::StringLiteral("a\n\"b\"\\".to_string(), Loc::default()).to_string(),
+            "\"a\\n\\\"b\\\"\\\\\""
+        );
+        assert_eq!(
+            Token::Comment("one\ntwo".to_string(), Loc::default()).to_string(),
+            "#one\n#two"
+        );
+    }
+
+    #[test]
+    fn test_format_indents_and_splits_lines() {
+        let input = r#"record Foo { name: string; }"#;
+        let tokens = tokenize(input).unwrap();
+
+        assert_eq!(format(&tokens), "record Foo {\n    name: string;\n}");
+    }
+
+    #[test]
+    fn test_format_round_trips_through_tokenize() {
+        let input = "@import \"common.djinni\"\n\nenum Color { red; green; blue; }";
+        let tokens = tokenize(input).unwrap();
+        let formatted = format(&tokens);
+
+        assert_eq!(tokenize(&formatted).unwrap(), tokens);
+    }
+
+    #[test]
+    fn test_format_does_not_space_method_call_parens() {
+        let input = "static create(): accounts_API;";
+        let tokens = tokenize(input).unwrap();
+
+        assert_eq!(format(&tokens), "static create(): accounts_API;\n");
+    }
+
+    #[test]
+    fn test_format_does_not_space_generic_angle_brackets() {
+        let input = "tags: list<string>;";
+        let tokens = tokenize(input).unwrap();
+
+        assert_eq!(format(&tokens), "tags: list<string>;\n");
+    }
+
+    #[test]
+    fn test_format_indents_multiline_comment_continuation_lines() {
+        let input = "record Foo {\n    # line one\n    # line two\n    name: string;\n}";
+        let tokens = tokenize(input).unwrap();
+
+        assert_eq!(
+            format(&tokens),
+            "record Foo {\n    # line one\n    # line two\n    name: string;\n}"
+        );
+    }
 }